@@ -0,0 +1,102 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use simple_error::bail;
+
+/// A storage backend that can version and sync the task data, independent of
+/// how that versioning is actually implemented. `GitBackend` is the only
+/// implementation today, but keeping this as a trait lets a future backend
+/// (e.g. a plain filesystem history or a different VCS) be swapped in
+/// without touching `ToDoList`.
+pub trait Backend: std::fmt::Debug {
+    /// Record the current state of `file` as a new revision.
+    fn save(&self, file: &Path) -> Result<(), Box<dyn Error>>;
+
+    /// Pull, then push, the data directory against `remote`.
+    fn sync(&self, remote: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Run an arbitrary command against the backend, for power users.
+    fn passthrough(&self, args: &[String]) -> Result<(), Box<dyn Error>>;
+}
+
+/// Versions the task data with a git repository rooted at `dir`, creating
+/// the repository on first use if it doesn't already exist.
+#[derive(Debug, Clone)]
+pub struct GitBackend {
+    dir: PathBuf,
+}
+
+impl GitBackend {
+    pub fn new(dir: PathBuf) -> Result<GitBackend, Box<dyn Error>> {
+        std::fs::create_dir_all(&dir)?;
+
+        let backend = GitBackend { dir };
+        if !backend.dir.join(".git").is_dir() {
+            backend.run_quiet(&["init"])?;
+        }
+        Ok(backend)
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new("git");
+        command.current_dir(&self.dir).args(args);
+        command
+    }
+
+    /// Run a git command as an implementation detail of `save`/`sync`,
+    /// suppressing its output so it doesn't pollute normal CLI output.
+    fn run_quiet(&self, args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let status = self
+            .command(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+
+        if !status.success() {
+            bail!("git {} exited with {}", args.join(" "), status);
+        }
+
+        Ok(())
+    }
+
+    /// Run a git command on the user's explicit behalf (the `git`
+    /// passthrough subcommand), letting its output through as normal.
+    fn run(&self, args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let status = self.command(args).status()?;
+
+        if !status.success() {
+            bail!("git {} exited with {}", args.join(" "), status);
+        }
+
+        Ok(())
+    }
+}
+
+impl Backend for GitBackend {
+    fn save(&self, file: &Path) -> Result<(), Box<dyn Error>> {
+        let name = file
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or("task file has no valid name")?;
+
+        self.run_quiet(&["add", name])?;
+
+        // Nothing to commit if the task file didn't change; that's not an
+        // error, so ignore a failing commit rather than bailing out.
+        let _ = self.run_quiet(&["commit", "-m", "Update tasks"]);
+
+        Ok(())
+    }
+
+    fn sync(&self, remote: &str) -> Result<(), Box<dyn Error>> {
+        self.run_quiet(&["pull", remote])?;
+        self.run_quiet(&["push", remote])?;
+        Ok(())
+    }
+
+    fn passthrough(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run(&args)
+    }
+}
@@ -0,0 +1,410 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+use std::ops::Drop;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use prettytable::*;
+use serde::{Deserialize, Serialize};
+use serde_json as json;
+use simple_error::bail;
+use tempfile::NamedTempFile;
+
+pub mod git;
+
+use git::Backend;
+
+pub static BACKUP_FILE: &str = "tasks.json";
+
+/// The directory `BACKUP_FILE` lives in, i.e. the data directory a storage
+/// backend should be rooted at. This is derived from `BACKUP_FILE` itself
+/// rather than the process's current directory, so the backend always
+/// points at wherever the task data actually is.
+pub fn data_dir() -> PathBuf {
+    Path::new(BACKUP_FILE)
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// How urgently a `Task` needs attention. Ordered so that `High < Medium <
+/// Low`, which lets `Print` sort tasks by ascending `Priority`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Priority {
+    High,
+    #[default]
+    Medium,
+    Low,
+}
+
+impl FromStr for Priority {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Priority, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "high" => Ok(Priority::High),
+            "medium" => Ok(Priority::Medium),
+            "low" => Ok(Priority::Low),
+            _ => bail!("unrecognized priority '{}' (expected high, medium, or low)", s),
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A single to-do item: a title plus the completion state, priority, and
+/// optional deadline needed to manage a real task list.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Task {
+    pub title: String,
+    pub priority: Priority,
+    pub deadline: Option<String>,
+    pub done: bool,
+}
+
+impl Task {
+    pub fn new(title: String, priority: Priority, deadline: Option<String>) -> Task {
+        Task {
+            title,
+            priority,
+            deadline,
+            done: false,
+        }
+    }
+}
+
+impl fmt::Display for Task {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.title)
+    }
+}
+
+/// A flattened, CSV-friendly view of a `Task` with its 1-based index, used
+/// for CSV import/export and for echoing a task added through `HookAdd`.
+#[derive(Serialize, Deserialize)]
+struct TaskRecord {
+    index: usize,
+    title: String,
+    priority: Priority,
+    deadline: Option<String>,
+    done: bool,
+}
+
+/// A reusable task definition containing `{{placeholder}}` tokens. Fields
+/// are kept as raw strings (rather than `Priority`/etc.) since they may not
+/// be fully filled in until `Instruction::FromTemplate` substitutes them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Template {
+    pub name: String,
+    pub title: String,
+    pub priority: String,
+    pub deadline: Option<String>,
+}
+
+/// Replace every `{{key}}` in `s` with its matching value from `params`,
+/// erroring out if any placeholder is left unfilled.
+fn substitute(s: &str, params: &[(String, String)]) -> Result<String, Box<dyn Error>> {
+    let mut out = s.to_string();
+    for (key, value) in params {
+        out = out.replace(&format!("{{{{{}}}}}", key), value);
+    }
+
+    if let Some(start) = out.find("{{") {
+        let end = out[start..].find("}}").map_or(out.len(), |e| start + e + 2);
+        bail!("unfilled placeholder '{}' in template", &out[start..end]);
+    }
+
+    Ok(out)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ToDoList {
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    templates: Vec<Template>,
+    name: String,
+    #[serde(skip)]
+    backend: Option<Box<dyn Backend>>,
+    /// Set once `save` has run successfully, so `Drop` knows not to redo the
+    /// work (and, with a git backend enabled, not to create a second commit)
+    /// when `main` has already saved explicitly.
+    #[serde(skip)]
+    saved: Cell<bool>,
+}
+
+impl ToDoList {
+    pub fn new(name: String) -> ToDoList {
+        ToDoList {
+            tasks: Vec::new(),
+            templates: Vec::new(),
+            name,
+            backend: None,
+            saved: Cell::new(false),
+        }
+    }
+
+    /// Enable versioned persistence: every future save will also record
+    /// `BACKUP_FILE` through `backend` (for a `GitBackend`, by staging and
+    /// committing it to the backend's repository).
+    pub fn with_backend(mut self, backend: Box<dyn Backend>) -> ToDoList {
+        self.backend = Some(backend);
+        self
+    }
+
+    pub fn run(&mut self, inst: Instruction<Task>) {
+        // Any instruction may change what needs to be persisted, so a prior
+        // `save` no longer counts; `Drop` must run again if `main` doesn't
+        // explicitly `save` after this.
+        self.saved.set(false);
+
+        match inst {
+            Instruction::Add(t) => self.tasks.push(t),
+            Instruction::Modify(i, t) => self.tasks[i - 1] = t,
+            Instruction::Remove(i) => {
+                self.tasks.remove(i - 1);
+            }
+            Instruction::Complete(i) => match self.tasks.get_mut(i.wrapping_sub(1)) {
+                Some(task) => task.done = true,
+                None => eprintln!("no task #{} to mark complete", i),
+            },
+            Instruction::AddTemplate(t) => self.templates.push(t),
+            Instruction::FromTemplate(name, params) => match self.instantiate(&name, &params) {
+                Ok(task) => self.tasks.push(task),
+                Err(e) => eprintln!("failed to instantiate template '{}': {}", name, e),
+            },
+            Instruction::HookAdd(t) => {
+                self.tasks.push(t);
+                let index = self.tasks.len();
+                let stored = &self.tasks[index - 1];
+                let record = TaskRecord {
+                    index,
+                    title: stored.title.clone(),
+                    priority: stored.priority,
+                    deadline: stored.deadline.clone(),
+                    done: stored.done,
+                };
+
+                match json::to_string(&record) {
+                    Ok(line) => println!("{}", line),
+                    Err(e) => eprintln!("failed to echo stored task: {}", e),
+                }
+            }
+            Instruction::Git(args) => match &self.backend {
+                Some(backend) => {
+                    if let Err(e) = backend.passthrough(&args) {
+                        eprintln!("git command failed: {}", e);
+                    }
+                }
+                None => eprintln!("no backend configured; nothing to run `git` against"),
+            },
+            Instruction::Sync(remote) => match &self.backend {
+                None => eprintln!("no backend configured; nothing to sync"),
+                Some(backend) => {
+                    // Commit the current tree first, so `sync` actually
+                    // pulls and pushes today's tasks rather than whatever
+                    // was last committed.
+                    if let Err(e) = self.save() {
+                        eprintln!("failed to save before sync: {}", e);
+                    } else if let Err(e) = backend.sync(&remote) {
+                        eprintln!("sync failed: {}", e);
+                    }
+                }
+            },
+            Instruction::Export(path) => {
+                if let Err(e) = self.export_csv(&path) {
+                    eprintln!("failed to export tasks to '{}': {}", path, e);
+                }
+            }
+            Instruction::Import(path) => {
+                if let Err(e) = self.import_csv(&path) {
+                    eprintln!("failed to import tasks from '{}': {}", path, e);
+                }
+            }
+            Instruction::Print => {
+                if !self.tasks.is_empty() {
+                    let mut rows: Vec<(usize, &Task)> = self.tasks.iter().enumerate().collect();
+                    rows.sort_by(|(_, a), (_, b)| {
+                        a.priority.cmp(&b.priority).then_with(|| {
+                            match (&a.deadline, &b.deadline) {
+                                (Some(x), Some(y)) => x.cmp(y),
+                                (Some(_), None) => Ordering::Less,
+                                (None, Some(_)) => Ordering::Greater,
+                                (None, None) => Ordering::Equal,
+                            }
+                        })
+                    });
+
+                    let mut table = Table::new();
+                    table.add_row(row!["#", "Done", "Task", "Priority", "Deadline"]);
+
+                    for (i, t) in rows {
+                        table.add_row(row![
+                            (i + 1).to_string(),
+                            if t.done { "[x]" } else { "[ ]" },
+                            t,
+                            t.priority,
+                            t.deadline.as_deref().unwrap_or("-")
+                        ]);
+                    }
+
+                    println!("\n\n{}'s To-Do List:\n", self.name);
+                    table.printstd();
+                } else {
+                    println!("No tasks to print for {}", self.name);
+                }
+            }
+        }
+    }
+
+    fn instantiate(&self, name: &str, params: &[(String, String)]) -> Result<Task, Box<dyn Error>> {
+        let template = self
+            .templates
+            .iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| format!("no template named '{}'", name))?;
+
+        let title = substitute(&template.title, params)?;
+        let priority = substitute(&template.priority, params)?.parse()?;
+        let deadline = template
+            .deadline
+            .as_deref()
+            .map(|d| substitute(d, params))
+            .transpose()?;
+
+        Ok(Task::new(title, priority, deadline))
+    }
+
+    fn export_csv(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut writer = csv::Writer::from_path(path)?;
+
+        for (i, t) in self.tasks.iter().enumerate() {
+            writer.serialize(TaskRecord {
+                index: i + 1,
+                title: t.title.clone(),
+                priority: t.priority,
+                deadline: t.deadline.clone(),
+                done: t.done,
+            })?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn import_csv(&mut self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut reader = csv::Reader::from_path(path)?;
+
+        for result in reader.deserialize() {
+            let record: TaskRecord = result?;
+            self.run(Instruction::Add(Task {
+                title: record.title,
+                priority: record.priority,
+                deadline: record.deadline,
+                done: record.done,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Persist the list to `BACKUP_FILE`, never leaving behind a partial
+    /// file: the serialized JSON is written to a sibling temporary file and
+    /// then atomically renamed over `BACKUP_FILE`, so a crash mid-write or a
+    /// serialization error can't corrupt or truncate the existing data.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let mut tmp = NamedTempFile::new_in(data_dir())?;
+        tmp.write_all(json::to_string_pretty(self)?.as_bytes())?;
+        tmp.persist(BACKUP_FILE)?;
+
+        if let Some(backend) = &self.backend {
+            backend.save(Path::new(BACKUP_FILE))?;
+        }
+
+        self.saved.set(true);
+        Ok(())
+    }
+}
+
+impl Drop for ToDoList {
+    fn drop(&mut self) {
+        // Best-effort fallback in case `main` exited before calling `save`
+        // explicitly; skip it entirely if that save already happened, so we
+        // don't redo the write (and, with a git backend, a second commit).
+        if self.saved.get() {
+            return;
+        }
+
+        if let Err(e) = self.save() {
+            eprintln!("warning: failed to save tasks: {}", e);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Instruction<T> {
+    Add(T),
+    Remove(usize),
+    Modify(usize, T),
+    Complete(usize),
+    /// Run `git <args>` directly against the backend's repository.
+    Git(Vec<String>),
+    /// Pull then push the backend's repository against `remote`.
+    Sync(String),
+    /// Export all tasks, as CSV, to the given file path.
+    Export(String),
+    /// Append tasks parsed from a CSV file at the given path.
+    Import(String),
+    /// Add a single task read from an external hook (e.g. piped stdin JSON).
+    HookAdd(T),
+    /// Register a reusable `Template` under its name.
+    AddTemplate(Template),
+    /// Instantiate the named template with the given `key=value` params.
+    FromTemplate(String, Vec<(String, String)>),
+    Print,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Instruction, Priority, Task, ToDoList};
+
+    #[test]
+    fn test_to_do_list() {
+        let mut list = ToDoList::new("Hari".to_string());
+
+        list.run(Instruction::Add(Task::new(
+            "Write Rust tutorial".to_string(),
+            Priority::Medium,
+            None,
+        )));
+        assert_eq!(list.tasks[0].title, "Write Rust tutorial");
+
+        list.run(Instruction::Modify(
+            1,
+            Task::new(
+                "Make fun of languages that aren't Rust".to_string(),
+                Priority::High,
+                None,
+            ),
+        ));
+        assert_eq!(list.tasks[0].title, "Make fun of languages that aren't Rust");
+
+        list.run(Instruction::Complete(1));
+        assert!(list.tasks[0].done);
+
+        list.run(Instruction::Remove(1));
+        assert!(list.tasks.is_empty());
+    }
+}
@@ -1,10 +1,11 @@
-use crate::api::Instruction;
+use crate::api::{Instruction, Priority, Task, Template};
 use std::error::Error;
+use std::io::{self, BufRead};
 
-use clap::clap_app;
+use clap::{clap_app, SubCommand};
 use simple_error::bail;
 
-pub fn parse() -> Result<Instruction<String>, Box<dyn Error>> {
+pub fn parse() -> Result<Instruction<Task>, Box<dyn Error>> {
     let matches = clap_app!(todo_cli =>
         (version: "0.1")
         (author: "USACS at Rutgers University")
@@ -13,6 +14,8 @@ pub fn parse() -> Result<Instruction<String>, Box<dyn Error>> {
         )
         (@subcommand add =>
            (@arg NEW: +required +takes_value "Task to add")
+           (@arg priority: -p --priority +takes_value "Priority of the task (high, medium, low)")
+           (@arg deadline: -d --deadline +takes_value "Deadline for the task")
            (about: "Add a task to CLI")
         )
         (@subcommand rm =>
@@ -22,23 +25,80 @@ pub fn parse() -> Result<Instruction<String>, Box<dyn Error>> {
         (@subcommand modify =>
             (@arg NUM: +required +takes_value "Identifier of task to modify")
             (@arg NEW: -n --new +required +takes_value "Task number to modify")
+            (@arg priority: -p --priority +takes_value "Priority of the task (high, medium, low)")
+            (@arg deadline: -d --deadline +takes_value "Deadline for the task")
             (about: "Modify a task stored by the CLI")
         )
+        (@subcommand done =>
+            (@arg NUM: +required +takes_value "Identifier of task to mark complete")
+            (about: "Mark a task as complete")
+        )
+        (@subcommand git =>
+            (@arg ARGS: +takes_value +multiple "Arguments to pass through to git")
+            (about: "Run a git command against the task data repository")
+        )
+        (@subcommand sync =>
+            (@arg REMOTE: +required +takes_value "Git remote to sync with")
+            (about: "Pull then push the task data repository against a remote")
+        )
+        (@subcommand import =>
+            (@arg file: -f --file +required +takes_value "CSV file to import tasks from")
+            (about: "Import tasks from a CSV file")
+        )
+        (@subcommand export =>
+            (@arg file: -f --file +required +takes_value "CSV file to export tasks to")
+            (about: "Export tasks to a CSV file")
+        )
+        (@subcommand template =>
+            (@arg NAME: +required +takes_value "Name to register the template under")
+            (@arg TITLE: +required +takes_value "Task title, may contain {{placeholders}}")
+            (@arg priority: -p --priority +takes_value "Priority, may contain {{placeholders}}")
+            (@arg deadline: -d --deadline +takes_value "Deadline, may contain {{placeholders}}")
+            (about: "Register a reusable task template")
+        )
+        (@subcommand from =>
+            (@arg NAME: +required +takes_value "Template to instantiate")
+            (@arg param: -p --param +takes_value +multiple "key=value substitution for a template placeholder")
+            (about: "Instantiate a template into a concrete task")
+        )
+    )
+    // clap_app!'s `@subcommand` identifiers can't contain hyphens, so
+    // `hook-add` is registered directly through the builder API instead.
+    .subcommand(
+        SubCommand::with_name("hook-add")
+            .about("Read a task as JSON from stdin and add it, echoing the stored task"),
     )
     .get_matches();
 
     if let Some(matches) = matches.subcommand_matches("add") {
-        return Ok(Instruction::Add(
+        let priority = match matches.value_of("priority") {
+            Some(p) => p.parse()?,
+            None => Priority::default(),
+        };
+        let deadline = matches.value_of("deadline").map(|d| d.to_string());
+
+        return Ok(Instruction::Add(Task::new(
             matches
                 .value_of("NEW")
                 .expect("Need task to add")
                 .to_string(),
-        ));
+            priority,
+            deadline,
+        )));
     } else if let Some(matches) = matches.subcommand_matches("rm") {
         return Ok(Instruction::Remove(
-            matches.value_of("NEW").expect("Need task to add").parse()?,
+            matches
+                .value_of("NUM")
+                .expect("Need identifier of task to remove")
+                .parse()?,
         ));
     } else if let Some(matches) = matches.subcommand_matches("modify") {
+        let priority = match matches.value_of("priority") {
+            Some(p) => p.parse()?,
+            None => Priority::default(),
+        };
+        let deadline = matches.value_of("deadline").map(|d| d.to_string());
+
         return Ok(Instruction::Modify(
             // This code might panic. Why? Exercise(Week 1): gracefully handle
             // the error case.
@@ -46,10 +106,95 @@ pub fn parse() -> Result<Instruction<String>, Box<dyn Error>> {
                 .value_of("NUM")
                 .expect("Need index of task to modify".as_ref())
                 .parse()?,
+            Task::new(
+                matches
+                    .value_of("NEW")
+                    .expect("Need task to modify to")
+                    .to_string(),
+                priority,
+                deadline,
+            ),
+        ));
+    } else if let Some(matches) = matches.subcommand_matches("done") {
+        return Ok(Instruction::Complete(
             matches
-                .value_of("NEW")
-                .expect("Need task to modify to")
+                .value_of("NUM")
+                .expect("Need index of task to complete")
+                .parse()?,
+        ));
+    } else if let Some(matches) = matches.subcommand_matches("git") {
+        let args = matches
+            .values_of("ARGS")
+            .map(|vals| vals.map(|v| v.to_string()).collect())
+            .unwrap_or_default();
+
+        return Ok(Instruction::Git(args));
+    } else if let Some(matches) = matches.subcommand_matches("sync") {
+        return Ok(Instruction::Sync(
+            matches
+                .value_of("REMOTE")
+                .expect("Need remote to sync with")
+                .to_string(),
+        ));
+    } else if let Some(matches) = matches.subcommand_matches("import") {
+        return Ok(Instruction::Import(
+            matches
+                .value_of("file")
+                .expect("Need file to import from")
+                .to_string(),
+        ));
+    } else if let Some(matches) = matches.subcommand_matches("export") {
+        return Ok(Instruction::Export(
+            matches
+                .value_of("file")
+                .expect("Need file to export to")
+                .to_string(),
+        ));
+    } else if matches.subcommand_matches("hook-add").is_some() {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+
+        let task: Task = serde_json::from_str(line.trim())?;
+        return Ok(Instruction::HookAdd(task));
+    } else if let Some(matches) = matches.subcommand_matches("template") {
+        let priority = match matches.value_of("priority") {
+            Some(p) => p.to_string(),
+            None => Priority::default().to_string(),
+        };
+        let deadline = matches.value_of("deadline").map(|d| d.to_string());
+
+        return Ok(Instruction::AddTemplate(Template {
+            name: matches
+                .value_of("NAME")
+                .expect("Need name to register template under")
+                .to_string(),
+            title: matches
+                .value_of("TITLE")
+                .expect("Need task title for template")
+                .to_string(),
+            priority,
+            deadline,
+        }));
+    } else if let Some(matches) = matches.subcommand_matches("from") {
+        let params = matches
+            .values_of("param")
+            .map(|vals| {
+                vals.map(|kv| {
+                    let mut parts = kv.splitn(2, '=');
+                    let key = parts.next().unwrap_or_default().to_string();
+                    let value = parts.next().unwrap_or_default().to_string();
+                    (key, value)
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
+        return Ok(Instruction::FromTemplate(
+            matches
+                .value_of("NAME")
+                .expect("Need name of template to instantiate")
                 .to_string(),
+            params,
         ));
     } else if matches.is_present("print") {
         return Ok(Instruction::Print);
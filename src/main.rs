@@ -5,11 +5,17 @@ use std::io::BufReader;
 mod api;
 mod cli;
 
-use crate::api::{ToDoList, BACKUP_FILE};
+use crate::api::git::GitBackend;
+use crate::api::{data_dir, ToDoList, BACKUP_FILE};
 use crate::cli::parse;
 
+/// Env var that opts into git-backed persistence; unset by default so that
+/// running the CLI doesn't silently turn whatever directory it's invoked
+/// from into a git repository.
+const GIT_BACKEND_VAR: &str = "TODO_CLI_GIT";
+
 pub(crate) fn main() -> Result<(), Box<dyn Error>> {
-    let mut list: ToDoList<String> = match File::open(BACKUP_FILE) {
+    let list: ToDoList = match File::open(BACKUP_FILE) {
         Ok(file) => {
             // file exists - deserialize and go with existing list
             let file = BufReader::new(file);
@@ -21,11 +27,12 @@ pub(crate) fn main() -> Result<(), Box<dyn Error>> {
         }
     };
 
-    Ok(list.run(parse()?))
-    // match parse() {
-    //     Some(inst) => list.run(inst),
-    //     None => panic!("Arguments could not be parsed"),
-    // }
+    let mut list = if std::env::var_os(GIT_BACKEND_VAR).is_some() {
+        list.with_backend(Box::new(GitBackend::new(data_dir())?))
+    } else {
+        list
+    };
 
-    // Ok(())
+    list.run(parse()?);
+    list.save()
 }